@@ -1,7 +1,32 @@
+//! By default this crate links against `std`. Disabling the default `std`
+//! feature builds against `core`/`alloc` instead (via an `extern crate alloc`),
+//! so the `Packet` wrapper can be linked into `no_std` firmware that already
+//! registers a global allocator. `PacketCursor`'s `Read`/`Write` impls require
+//! `std`, since those traits live in `std::io`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
 mod bindings;
 
 use core::ffi;
-use std::{ops::Index, slice};
+use core::marker::PhantomData;
+use core::ops::{
+    Bound, Index, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo,
+    RangeToInclusive,
+};
+use core::slice;
+
+#[cfg(not(feature = "std"))]
+use alloc::alloc::{alloc, dealloc, Layout};
+#[cfg(feature = "std")]
+use std::alloc::{alloc, dealloc, Layout};
+
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "std")]
+use std::sync::Mutex;
 
 /// Macro to create a Packet with the given elements
 ///
@@ -59,21 +84,19 @@ macro_rules! packet {
 /// Rust wrapper for the C Packet struct
 pub struct Packet {
     ptr: *mut bindings::Packet,
-    layout: std::alloc::Layout,
+    layout: Layout,
 }
 
 impl Packet {
     /// Create a new Packet with the specified length
     pub fn new(length: u16) -> Option<Self> {
-        let data_size = length as usize * std::mem::size_of::<i32>();
-        let total_size = std::mem::size_of::<bindings::Packet>() + data_size;
+        let data_size = length as usize * core::mem::size_of::<i32>();
+        let total_size = core::mem::size_of::<bindings::Packet>() + data_size;
 
-        let layout = std::alloc::Layout::from_size_align(
-            total_size,
-            std::mem::align_of::<bindings::Packet>(),
-        )
-        .ok()?;
-        let ptr = unsafe { std::alloc::alloc(layout) as *mut bindings::Packet };
+        let layout =
+            Layout::from_size_align(total_size, core::mem::align_of::<bindings::Packet>())
+                .ok()?;
+        let ptr = unsafe { alloc(layout) as *mut bindings::Packet };
 
         if ptr.is_null() {
             return None;
@@ -138,6 +161,70 @@ impl Packet {
         unsafe { slice::from_raw_parts_mut((*self.ptr).data.as_mut_ptr(), length) }
     }
 
+    /// Get the first element, or `None` if the packet is empty.
+    pub fn first(&self) -> Option<&i32> {
+        self.data().first()
+    }
+
+    /// Get a mutable reference to the first element, or `None` if the packet
+    /// is empty.
+    pub fn first_mut(&mut self) -> Option<&mut i32> {
+        self.data_mut().first_mut()
+    }
+
+    /// Get the last element, or `None` if the packet is empty.
+    pub fn last(&self) -> Option<&i32> {
+        self.data().last()
+    }
+
+    /// Get a mutable reference to the last element, or `None` if the packet
+    /// is empty.
+    pub fn last_mut(&mut self) -> Option<&mut i32> {
+        self.data_mut().last_mut()
+    }
+
+    /// Split the first element from the rest, or `None` if the packet is
+    /// empty.
+    pub fn split_first(&self) -> Option<(&i32, &[i32])> {
+        self.data().split_first()
+    }
+
+    /// Split the first element from the rest, or `None` if the packet is
+    /// empty.
+    pub fn split_first_mut(&mut self) -> Option<(&mut i32, &mut [i32])> {
+        self.data_mut().split_first_mut()
+    }
+
+    /// Split the last element from the rest, or `None` if the packet is
+    /// empty.
+    pub fn split_last(&self) -> Option<(&i32, &[i32])> {
+        self.data().split_last()
+    }
+
+    /// Split the last element from the rest, or `None` if the packet is
+    /// empty.
+    pub fn split_last_mut(&mut self) -> Option<(&mut i32, &mut [i32])> {
+        self.data_mut().split_last_mut()
+    }
+
+    /// Divide the packet's data into two slices at `mid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > self.len()`.
+    pub fn split_at(&self, mid: usize) -> (&[i32], &[i32]) {
+        self.data().split_at(mid)
+    }
+
+    /// Divide the packet's data into two mutable slices at `mid`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > self.len()`.
+    pub fn split_at_mut(&mut self, mid: usize) -> (&mut [i32], &mut [i32]) {
+        self.data_mut().split_at_mut(mid)
+    }
+
     /// Get the raw pointer
     pub fn as_ptr(&self) -> *const bindings::Packet {
         self.ptr
@@ -147,6 +234,174 @@ impl Packet {
     pub fn as_mut_ptr(&mut self) -> *mut bindings::Packet {
         self.ptr
     }
+
+    /// Get a byte-oriented cursor over the packet's data, implementing
+    /// [`Read`] and [`Write`] so the fixed-size C buffer can be driven
+    /// through any `Read`/`Write` adapter. Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn cursor_mut(&mut self) -> PacketCursor<'_> {
+        let data = self.data_mut();
+        let bytes = unsafe {
+            slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, core::mem::size_of_val(data))
+        };
+        PacketCursor { bytes, pos: 0 }
+    }
+
+    /// Remove the elements in `range`, shifting the remaining elements down
+    /// and returning an iterator that yields the removed values.
+    ///
+    /// Mirrors [`Vec::drain`]: if the returned [`Drain`] is leaked (e.g. via
+    /// `mem::forget`) instead of dropped, the packet is left holding only the
+    /// elements before `range.start` -- a consistent, possibly-shorter state,
+    /// the same leak-amplification behavior `Vec::drain` documents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end, or if the
+    /// end of the range is greater than the packet's length.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        let len = self.len() as usize;
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n + 1,
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => len,
+        };
+
+        assert!(
+            start <= end,
+            "drain start index (is {start}) should be <= end index (is {end})"
+        );
+        assert!(
+            end <= len,
+            "drain end index (is {end}) should be <= packet length (is {len})"
+        );
+
+        // Shrink the packet's visible length to `start` up front so that if
+        // `Drain` is leaked, the packet still only exposes the surviving prefix.
+        unsafe {
+            (*self.ptr).length = start as u16;
+        }
+
+        Drain {
+            ptr: self.ptr,
+            start,
+            tail_start: end,
+            tail_len: len - end,
+            iter: start..end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A draining iterator over a range of a [`Packet`]'s elements, created by
+/// [`Packet::drain`].
+pub struct Drain<'a> {
+    ptr: *mut bindings::Packet,
+    start: usize,
+    tail_start: usize,
+    tail_len: usize,
+    iter: Range<usize>,
+    _marker: PhantomData<&'a mut Packet>,
+}
+
+impl Iterator for Drain<'_> {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        self.iter
+            .next()
+            .map(|i| unsafe { *(*self.ptr).data.as_ptr().add(i) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    fn next_back(&mut self) -> Option<i32> {
+        self.iter
+            .next_back()
+            .map(|i| unsafe { *(*self.ptr).data.as_ptr().add(i) })
+    }
+}
+
+impl ExactSizeIterator for Drain<'_> {}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            let data_ptr = (*self.ptr).data.as_mut_ptr();
+            if self.tail_len > 0 {
+                core::ptr::copy(
+                    data_ptr.add(self.tail_start),
+                    data_ptr.add(self.start),
+                    self.tail_len,
+                );
+            }
+            (*self.ptr).length = (self.start + self.tail_len) as u16;
+        }
+    }
+}
+
+/// A byte-oriented cursor over a [`Packet`]'s `[i32]` data, reinterpreted as
+/// `&mut [u8]`, returned by [`Packet::cursor_mut`].
+///
+/// Reads mirror [`Read::read_exact`]: if fewer bytes remain than requested,
+/// `read_exact` fails with `ErrorKind::UnexpectedEof` without consuming any
+/// bytes. Writes fail with `ErrorKind::WriteZero` once the packet's fixed
+/// capacity is exhausted.
+#[cfg(feature = "std")]
+pub struct PacketCursor<'a> {
+    bytes: &'a mut [u8],
+    pos: usize,
+}
+
+#[cfg(feature = "std")]
+impl Read for PacketCursor<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = &self.bytes[self.pos..];
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let remaining = &self.bytes[self.pos..];
+        if remaining.len() < buf.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ));
+        }
+        buf.copy_from_slice(&remaining[..buf.len()]);
+        self.pos += buf.len();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Write for PacketCursor<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let remaining = &mut self.bytes[self.pos..];
+        if remaining.is_empty() && !buf.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "packet is full"));
+        }
+        let n = remaining.len().min(buf.len());
+        remaining[..n].copy_from_slice(&buf[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl<'a> IntoIterator for &'a Packet {
@@ -167,8 +422,8 @@ impl<'a> IntoIterator for &'a mut Packet {
     }
 }
 
-impl std::fmt::Debug for Packet {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for Packet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Packet")
             .field("length", &self.len())
             .field("data", &self.data())
@@ -176,8 +431,8 @@ impl std::fmt::Debug for Packet {
     }
 }
 
-impl std::fmt::Display for Packet {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Packet {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Packet(length: {}, data: {:?})", self.len(), self.data())
     }
 }
@@ -186,7 +441,7 @@ impl Drop for Packet {
     fn drop(&mut self) {
         if !self.ptr.is_null() {
             unsafe {
-                std::alloc::dealloc(self.ptr as *mut u8, self.layout);
+                dealloc(self.ptr as *mut u8, self.layout);
             }
         }
     }
@@ -211,58 +466,120 @@ impl Index<usize> for Packet {
     }
 }
 
-impl Index<std::ops::Range<usize>> for Packet {
+impl Index<Range<usize>> for Packet {
     type Output = [i32];
 
-    fn index(&self, range: std::ops::Range<usize>) -> &Self::Output {
+    fn index(&self, range: Range<usize>) -> &Self::Output {
         &self.data()[range]
     }
 }
 
-impl Index<std::ops::RangeFrom<usize>> for Packet {
+impl Index<RangeFrom<usize>> for Packet {
     type Output = [i32];
 
-    fn index(&self, range: std::ops::RangeFrom<usize>) -> &Self::Output {
+    fn index(&self, range: RangeFrom<usize>) -> &Self::Output {
         &self.data()[range]
     }
 }
 
-impl Index<std::ops::RangeTo<usize>> for Packet {
+impl Index<RangeTo<usize>> for Packet {
     type Output = [i32];
 
-    fn index(&self, range: std::ops::RangeTo<usize>) -> &Self::Output {
+    fn index(&self, range: RangeTo<usize>) -> &Self::Output {
         &self.data()[range]
     }
 }
 
-impl Index<std::ops::RangeFull> for Packet {
+impl Index<RangeFull> for Packet {
     type Output = [i32];
 
-    fn index(&self, _range: std::ops::RangeFull) -> &Self::Output {
+    fn index(&self, _range: RangeFull) -> &Self::Output {
         self.data()
     }
 }
 
-impl Index<std::ops::RangeInclusive<usize>> for Packet {
+impl Index<RangeInclusive<usize>> for Packet {
     type Output = [i32];
 
-    fn index(&self, range: std::ops::RangeInclusive<usize>) -> &Self::Output {
+    fn index(&self, range: RangeInclusive<usize>) -> &Self::Output {
         &self.data()[range]
     }
 }
 
-impl Index<std::ops::RangeToInclusive<usize>> for Packet {
+impl Index<RangeToInclusive<usize>> for Packet {
     type Output = [i32];
 
-    fn index(&self, range: std::ops::RangeToInclusive<usize>) -> &Self::Output {
+    fn index(&self, range: RangeToInclusive<usize>) -> &Self::Output {
         &self.data()[range]
     }
 }
 
+/// A thread-safe wrapper around [`Packet`], guarding access with a
+/// [`Mutex`] so a packet can be shared across threads.
+///
+/// `Packet` holds a raw `*mut bindings::Packet`, so it is neither `Send` nor
+/// `Sync` on its own. `SharedPacket` is `Send`/`Sync` because the allocation
+/// behind the pointer is exclusively owned by the `Mutex` -- every access
+/// goes through `lock()` except where a unique `&mut SharedPacket` already
+/// rules out concurrent access -- and `bindings::get_packet_len` (used by
+/// `Packet::len`) is a read-only, reentrant C call safe to invoke from any
+/// thread.
+///
+/// # Safety
+///
+/// This relies on the C library never retaining the `Packet` pointer beyond
+/// the call that received it; if the C side stored the pointer and accessed
+/// it from another thread outside of `lock()`, the `Send`/`Sync` impls below
+/// would be unsound.
+#[cfg(feature = "std")]
+pub struct SharedPacket {
+    inner: Mutex<Packet>,
+}
+
+#[cfg(feature = "std")]
+impl SharedPacket {
+    /// Wrap `packet` for sharing across threads.
+    pub fn new(packet: Packet) -> Self {
+        Self {
+            inner: Mutex::new(packet),
+        }
+    }
+
+    /// Acquire the lock, blocking until it is available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex is poisoned, where [`Mutex::lock`] would return an
+    /// error instead.
+    pub fn lock(&self) -> std::sync::MutexGuard<'_, Packet> {
+        self.inner.lock().expect("SharedPacket mutex poisoned")
+    }
+
+    /// Get a mutable reference to the inner packet without locking, since a
+    /// unique borrow of `self` already rules out concurrent access.
+    pub fn get_mut(&mut self) -> &mut Packet {
+        self.inner.get_mut().expect("SharedPacket mutex poisoned")
+    }
+
+    /// Consume the wrapper, recovering ownership of the inner packet.
+    pub fn into_inner(self) -> Packet {
+        self.inner.into_inner().expect("SharedPacket mutex poisoned")
+    }
+}
+
+// SAFETY: see the safety invariant documented on `SharedPacket` above.
+#[cfg(feature = "std")]
+unsafe impl Send for SharedPacket {}
+#[cfg(feature = "std")]
+unsafe impl Sync for SharedPacket {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
     #[test]
     fn test_packet_wrapper() {
         // Create a new packet wrapper
@@ -357,4 +674,185 @@ mod tests {
         assert_eq!(&packet[..=0], &[1]);
         assert_eq!(&packet[2..=2], &[3]);
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cursor_read_and_write_roundtrip() {
+        let mut packet = Packet::new(2).expect("Failed to create packet");
+        {
+            let mut cursor = packet.cursor_mut();
+            let written = cursor.write(&1i32.to_ne_bytes()).expect("write failed");
+            assert_eq!(written, 4);
+            let written = cursor.write(&2i32.to_ne_bytes()).expect("write failed");
+            assert_eq!(written, 4);
+        }
+        assert_eq!(packet.data(), &[1, 2]);
+
+        let mut cursor = packet.cursor_mut();
+        let mut buf = [0u8; 4];
+        cursor.read_exact(&mut buf).expect("read_exact failed");
+        assert_eq!(i32::from_ne_bytes(buf), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cursor_read_exact_unexpected_eof() {
+        let mut packet = Packet::new(1).expect("Failed to create packet");
+        let mut cursor = packet.cursor_mut();
+        let mut buf = [0u8; 8];
+        let err = cursor.read_exact(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cursor_write_zero_when_full() {
+        let mut packet = Packet::new(1).expect("Failed to create packet");
+        let mut cursor = packet.cursor_mut();
+        cursor.write_all(&[1, 2, 3, 4]).expect("write_all failed");
+        let err = cursor.write(&[5]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_drain_middle_range() {
+        let mut packet = packet![1, 2, 3, 4, 5];
+        let drained: Vec<i32> = packet.drain(1..3).collect();
+        assert_eq!(drained, vec![2, 3]);
+        assert_eq!(packet.len(), 3);
+        assert_eq!(packet.data(), &[1, 4, 5]);
+    }
+
+    #[test]
+    fn test_drain_prefix() {
+        let mut packet = packet![1, 2, 3, 4, 5];
+        let drained: Vec<i32> = packet.drain(..2).collect();
+        assert_eq!(drained, vec![1, 2]);
+        assert_eq!(packet.len(), 3);
+        assert_eq!(packet.data(), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn test_drain_full_range() {
+        let mut packet = packet![1, 2, 3];
+        let drained: Vec<i32> = packet.drain(..).collect();
+        assert_eq!(drained, vec![1, 2, 3]);
+        assert_eq!(packet.len(), 0);
+        assert!(packet.is_empty());
+    }
+
+    #[test]
+    fn test_drain_empty_range() {
+        let mut packet = packet![1, 2, 3];
+        let drained: Vec<i32> = packet.drain(1..1).collect();
+        assert!(drained.is_empty());
+        assert_eq!(packet.len(), 3);
+        assert_eq!(packet.data(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let mut packet = packet![1, 2, 3];
+        assert_eq!(packet.first(), Some(&1));
+        assert_eq!(packet.last(), Some(&3));
+
+        *packet.first_mut().unwrap() = 10;
+        *packet.last_mut().unwrap() = 30;
+        assert_eq!(packet.data(), &[10, 2, 30]);
+
+        let empty = packet![];
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+    }
+
+    #[test]
+    fn test_split_first_and_last() {
+        let packet = packet![1, 2, 3];
+        assert_eq!(packet.split_first(), Some((&1, &[2, 3][..])));
+        assert_eq!(packet.split_last(), Some((&3, &[1, 2][..])));
+
+        let empty = packet![];
+        assert_eq!(empty.split_first(), None);
+        assert_eq!(empty.split_last(), None);
+    }
+
+    #[test]
+    fn test_split_first_last_mut() {
+        let mut packet = packet![1, 2, 3];
+        let (first, rest) = packet.split_first_mut().unwrap();
+        *first = 10;
+        rest[0] = 20;
+        assert_eq!(packet.data(), &[10, 20, 3]);
+
+        let (last, rest) = packet.split_last_mut().unwrap();
+        *last = 30;
+        rest[0] = 11;
+        assert_eq!(packet.data(), &[11, 20, 30]);
+    }
+
+    #[test]
+    fn test_split_at() {
+        let packet = packet![1, 2, 3, 4, 5];
+        let (left, right) = packet.split_at(2);
+        assert_eq!(left, &[1, 2]);
+        assert_eq!(right, &[3, 4, 5]);
+    }
+
+    #[test]
+    fn test_split_at_mut() {
+        let mut packet = packet![1, 2, 3, 4, 5];
+        let (left, right) = packet.split_at_mut(2);
+        left[0] = 10;
+        right[0] = 30;
+        assert_eq!(packet.data(), &[10, 2, 30, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_split_at_out_of_bounds() {
+        let packet = packet![1, 2, 3];
+        let _ = packet.split_at(10);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_shared_packet_send_across_thread() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let shared = Arc::new(SharedPacket::new(packet![1, 2, 3]));
+
+        let writer = Arc::clone(&shared);
+        thread::spawn(move || {
+            let mut guard = writer.lock();
+            guard.data_mut()[0] = 10;
+        })
+        .join()
+        .expect("writer thread panicked");
+
+        let guard = shared.lock();
+        assert_eq!(guard.data(), &[10, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_shared_packet_get_mut_and_into_inner() {
+        let mut shared = SharedPacket::new(packet![1, 2, 3]);
+        shared.get_mut().data_mut()[1] = 20;
+
+        let packet = shared.into_inner();
+        assert_eq!(packet.data(), &[1, 20, 3]);
+    }
+
+    #[test]
+    fn test_drain_partial_consumption_still_shifts_tail() {
+        let mut packet = packet![1, 2, 3, 4, 5];
+        {
+            let mut drain = packet.drain(1..4);
+            assert_eq!(drain.next(), Some(2));
+            // The rest of the drained range is dropped without being consumed.
+        }
+        assert_eq!(packet.len(), 2);
+        assert_eq!(packet.data(), &[1, 5]);
+    }
 }